@@ -3,7 +3,9 @@ use core::sync::atomic::{AtomicUsize, Ordering};
 use crossbeam_utils::CachePadded;
 use rustc_hash::FxHashSet;
 
+use crate::fence;
 use crate::hazard::ThreadRecords;
+use crate::probes::probe;
 use crate::retire::RetiredList;
 use crate::thread::Thread;
 
@@ -58,17 +60,21 @@ pub(crate) struct EpochBarrier(AtomicUsize);
 impl EpochBarrier {
     pub(crate) fn barrier(&self) {
         let epoch = self.0.load(Ordering::Acquire);
-        membarrier::heavy();
+        fence::heavy();
         let new_epoch = epoch.wrapping_add(1);
-        let _ = self
+        if self
             .0
-            .compare_exchange(epoch, new_epoch, Ordering::Release, Ordering::Acquire);
+            .compare_exchange(epoch, new_epoch, Ordering::Release, Ordering::Acquire)
+            .is_ok()
+        {
+            probe!(epoch_advance, new_epoch);
+        }
     }
 
     pub(crate) fn read(&self) -> usize {
         let mut epoch = self.0.load(Ordering::Acquire);
         loop {
-            membarrier::light_membarrier();
+            fence::light_membarrier();
             let new_epoch = self.0.load(Ordering::Acquire);
             if epoch == new_epoch {
                 return epoch;
@@ -0,0 +1,38 @@
+//! Asymmetric fences used by protection (light) and reclamation (heavy).
+//!
+//! Miri supports neither the `membarrier` syscall nor its `mprotect` fallback, so under Miri both
+//! sides are plain `SeqCst` fences. This is stronger than the asymmetric pair.
+//!
+//! ThreadSanitizer models neither `membarrier` nor standalone fences. With the `tsan` feature, both
+//! sides are a `SeqCst` RMW on one shared atomic instead. TSan does track those, and the RMWs are
+//! totally ordered, so whichever side comes second synchronizes with the store before the first.
+
+#[cfg(not(any(miri, feature = "tsan")))]
+pub use membarrier::{heavy, light_membarrier};
+
+#[cfg(miri)]
+#[inline]
+pub fn light_membarrier() {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(miri)]
+#[inline]
+pub fn heavy() {
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(all(feature = "tsan", not(miri)))]
+static SYNC: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+#[cfg(all(feature = "tsan", not(miri)))]
+#[inline]
+pub fn light_membarrier() {
+    SYNC.fetch_add(0, core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(all(feature = "tsan", not(miri)))]
+#[inline]
+pub fn heavy() {
+    SYNC.fetch_add(0, core::sync::atomic::Ordering::SeqCst);
+}
@@ -2,6 +2,7 @@ use core::marker::PhantomData;
 use core::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
 use core::{mem, ptr};
 
+use crate::fence;
 use crate::thread::Thread;
 use crate::untagged;
 use crate::DEFAULT_THREAD;
@@ -53,7 +54,7 @@ impl<'domain> HazardPointer<'domain> {
     /// For a pointer `p`, if "`src` still pointing to `pointer`" implies that `p` is not retired,
     /// then `Ok(())` means that shields set to `p` are validated.
     pub fn validate<T>(pointer: *mut T, src: &AtomicPtr<T>) -> Result<(), *mut T> {
-        membarrier::light_membarrier();
+        fence::light_membarrier();
         let new = src.load(Ordering::Acquire);
         if pointer == new {
             Ok(())
@@ -94,7 +95,7 @@ impl<'domain> HazardPointer<'domain> {
         F: Fn(&S) -> bool,
     {
         self.protect_raw(ptr);
-        membarrier::light_membarrier();
+        fence::light_membarrier();
         if is_invalid(src) {
             return Err(ProtectError::Invalidated);
         }
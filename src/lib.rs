@@ -1,12 +1,14 @@
 mod domain;
+mod fence;
 mod hazard;
+mod probes;
 mod retire;
 mod tag;
 mod thread;
 
+pub use fence::light_membarrier;
 pub use hazard::HazardPointer;
 pub use hazard::ProtectError;
-pub use membarrier::light_membarrier;
 pub use tag::*;
 pub use thread::set_counts_between_flush;
 
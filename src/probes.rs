@@ -0,0 +1,70 @@
+//! Static tracepoints at reclamation events.
+//!
+//! With the `usdt` feature on x86-64 Linux, these are SystemTap SDT probes under the `hp_pp`
+//! provider, so bpftrace/DTrace scripts can attach to a running process (e.g.
+//! `usdt:*:hp_pp:reclaim_end`). Otherwise they expand to nothing.
+//!
+//! Each probe site is a `nop` plus an entry in the `.note.stapsdt` section recording its address
+//! and the registers holding its arguments, following the note format of `<sys/sdt.h>`. The
+//! `.stapsdt.base` section is marked retained so that `--gc-sections` keeps it.
+//!
+//! | probe           | arg0                      | arg1             |
+//! |-----------------|---------------------------|------------------|
+//! | `retire`        | retired pointer           |                  |
+//! | `invalidate`    | number of nodes (HP++)    |                  |
+//! | `epoch_advance` | new epoch                 |                  |
+//! | `reclaim_start` | number of retired nodes   |                  |
+//! | `reclaim_end`   | number of freed nodes     | number not freed |
+//!
+//! `retire` fires for both plain `retire` and nodes retired through `try_unlink`.
+
+#[cfg(all(feature = "usdt", target_os = "linux", target_arch = "x86_64"))]
+macro_rules! probe {
+    (@note $name:ident, $args:expr $(, $arg:expr)*) => {
+        // SAFETY: only emits a `nop` and note/comdat sections; no registers or memory are written.
+        unsafe {
+            core::arch::asm!(
+                "990: nop",
+                ".pushsection .note.stapsdt, \"\", @note",
+                ".balign 4",
+                ".4byte 992f - 991f, 994f - 993f, 3",
+                "991: .asciz \"stapsdt\"",
+                "992: .balign 4",
+                "993: .8byte 990b",
+                ".8byte _.stapsdt.base",
+                ".8byte 0",
+                ".asciz \"hp_pp\"",
+                concat!(".asciz \"", stringify!($name), "\""),
+                concat!(".asciz \"", $args, "\""),
+                "994: .balign 4",
+                ".popsection",
+                ".ifndef _.stapsdt.base",
+                ".pushsection .stapsdt.base, \"aGR\", @progbits, .stapsdt.base, comdat",
+                ".weak _.stapsdt.base",
+                ".hidden _.stapsdt.base",
+                "_.stapsdt.base: .space 1",
+                ".size _.stapsdt.base, 1",
+                ".popsection",
+                ".endif",
+                $(in(reg) ($arg) as usize,)*
+                options(att_syntax, nomem, nostack, preserves_flags),
+            )
+        }
+    };
+    ($name:ident) => {
+        probe!(@note $name, "")
+    };
+    ($name:ident, $a0:expr) => {
+        probe!(@note $name, "8@{0}", $a0)
+    };
+    ($name:ident, $a0:expr, $a1:expr) => {
+        probe!(@note $name, "8@{0} 8@{1}", $a0, $a1)
+    };
+}
+
+#[cfg(not(all(feature = "usdt", target_os = "linux", target_arch = "x86_64")))]
+macro_rules! probe {
+    ($($tt:tt)*) => {};
+}
+
+pub(crate) use probe;
@@ -2,6 +2,7 @@ use core::ptr;
 use core::sync::atomic::{AtomicPtr, Ordering};
 use std::mem;
 
+use crate::probes::probe;
 use crate::{HazardPointer, Invalidate};
 
 #[derive(Debug, Clone, Copy)]
@@ -43,6 +44,7 @@ impl<'domain> Unlinked<'domain> {
         let mut retireds = Vec::with_capacity(self.ptrs.len());
         for ptr in self.ptrs {
             unsafe { (self.invalidater)(ptr) };
+            probe!(retire, ptr as usize);
             retireds.push(Retired {
                 ptr,
                 deleter: self.deleter,
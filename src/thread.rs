@@ -1,15 +1,16 @@
-use core::sync::atomic::{AtomicPtr, Ordering};
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
 use core::{mem, ptr};
 use std::collections::VecDeque;
 
 use crate::domain::Domain;
 use crate::domain::EpochBarrier;
 use crate::hazard::ThreadRecord;
+use crate::probes::probe;
 use crate::retire::{Retired, Unlinked};
 use crate::HazardPointer;
 use crate::{Invalidate, Unlink};
 
-pub static mut COUNTS_BETWEEN_FLUSH: usize = 64;
+pub static COUNTS_BETWEEN_FLUSH: AtomicUsize = AtomicUsize::new(64);
 
 #[inline]
 pub fn set_counts_between_flush(counts: usize) {
@@ -17,22 +18,22 @@ pub fn set_counts_between_flush(counts: usize) {
         counts >= 2 && counts % 2 == 0,
         "counts must be even and greater than 1."
     );
-    unsafe { COUNTS_BETWEEN_FLUSH = counts };
+    COUNTS_BETWEEN_FLUSH.store(counts, Ordering::Relaxed);
 }
 
 #[inline]
 pub fn counts_between_invalidation() -> usize {
-    unsafe { COUNTS_BETWEEN_FLUSH / 2 }
+    COUNTS_BETWEEN_FLUSH.load(Ordering::Relaxed) / 2
 }
 
 #[inline]
 pub fn counts_between_flush() -> usize {
-    unsafe { COUNTS_BETWEEN_FLUSH }
+    COUNTS_BETWEEN_FLUSH.load(Ordering::Relaxed)
 }
 
 #[inline]
 pub fn counts_between_collect() -> usize {
-    unsafe { COUNTS_BETWEEN_FLUSH * 2 }
+    COUNTS_BETWEEN_FLUSH.load(Ordering::Relaxed) * 2
 }
 
 pub struct Thread<'domain> {
@@ -81,6 +82,7 @@ impl<'domain> Thread<'domain> {
     #[inline]
     pub unsafe fn retire<T>(&mut self, ptr: *mut T) {
         self.retired.push(Retired::new(ptr));
+        probe!(retire, ptr as usize);
         let count = self.count.wrapping_add(1);
         self.count = count;
         if count % counts_between_flush() == 0 {
@@ -145,6 +147,9 @@ impl<'domain> Thread<'domain> {
             invalidateds.append(&mut ptrs);
             hps.append(&mut hs);
         }
+        if !invalidateds.is_empty() {
+            probe!(invalidate, invalidateds.len());
+        }
 
         let epoch = self.domain.barrier.read();
         while let Some(&(old_epoch, _)) = self.epoched_hps.front() {
@@ -167,6 +172,7 @@ impl<'domain> Thread<'domain> {
             return;
         }
 
+        probe!(reclaim_start, retireds_len);
         self.domain.barrier.barrier();
 
         // only for hp++, but this doesn't introduce big cost for plain hp.
@@ -187,6 +193,7 @@ impl<'domain> Thread<'domain> {
         self.domain
             .num_garbages
             .fetch_sub(retireds_len - not_freed.len(), Ordering::AcqRel);
+        probe!(reclaim_end, retireds_len - not_freed.len(), not_freed.len());
         self.domain.retireds.push(not_freed);
     }
 }
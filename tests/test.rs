@@ -1,4 +1,4 @@
-use core::sync::atomic::{AtomicPtr, Ordering::*};
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering::*};
 use std::thread::sleep;
 use std::time::Duration;
 
@@ -7,10 +7,17 @@ use queue::Queue;
 use stack::Stack;
 use std::thread::scope;
 
+/// Miri and ThreadSanitizer are too slow for the full-size runs. Shrunk runs also flush and
+/// collect every couple of retires, so that the few operations they do still reach reclamation.
+const SHRINK: bool = cfg!(any(miri, feature = "tsan"));
+
 #[test]
 fn counter() {
-    const THREADS: usize = 4;
-    const ITER: usize = 1024 * 16;
+    const THREADS: usize = if SHRINK { 2 } else { 4 };
+    const ITER: usize = if SHRINK { 64 } else { 1024 * 16 };
+    if SHRINK {
+        set_counts_between_flush(2);
+    }
 
     let count = AtomicPtr::new(Box::leak(Box::new(0usize)));
     scope(|s| {
@@ -48,58 +55,82 @@ fn counter() {
 // `do_reclamation`.
 #[test]
 fn counter_sleep() {
-    const THREADS: usize = 4;
-    const ITER: usize = 1024 * 16;
+    const THREADS: usize = if SHRINK { 2 } else { 4 };
+    const ITER: usize = if SHRINK { 16 } else { 1024 * 16 };
+    if SHRINK {
+        set_counts_between_flush(2);
+    }
 
-    let count = AtomicPtr::new(Box::leak(Box::new(0usize)));
-    scope(|s| {
-        for _ in 0..THREADS {
-            s.spawn(|| {
-                for _ in 0..ITER {
-                    let mut new = Box::new(0);
-                    let mut hp = HazardPointer::default();
-                    loop {
-                        let cur_ptr = {
-                            let mut cur = count.load(Relaxed);
-                            loop {
-                                match hp.try_protect(cur, &count) {
-                                    Ok(_) => break cur,
-                                    Err(new) => {
-                                        sleep(Duration::from_micros(1));
-                                        cur = new;
+    static FREED: AtomicUsize = AtomicUsize::new(0);
+    struct Value(usize);
+    impl Drop for Value {
+        fn drop(&mut self) {
+            FREED.fetch_add(1, Relaxed);
+        }
+    }
+
+    let count = AtomicPtr::new(Box::leak(Box::new(Value(0))));
+    let freed_before_exit = scope(|s| {
+        let handles = (0..THREADS)
+            .map(|_| {
+                s.spawn(|| {
+                    for _ in 0..ITER {
+                        let mut new = Box::new(Value(0));
+                        let mut hp = HazardPointer::default();
+                        loop {
+                            let cur_ptr = {
+                                let mut cur = count.load(Relaxed);
+                                loop {
+                                    match hp.try_protect(cur, &count) {
+                                        Ok(_) => break cur,
+                                        Err(new) => {
+                                            sleep(Duration::from_micros(1));
+                                            cur = new;
+                                        }
                                     }
                                 }
+                            };
+                            sleep(Duration::from_micros(1));
+                            let value = unsafe { (*cur_ptr).0 };
+                            new.0 = value + 1;
+                            let new_ptr = Box::leak(new);
+                            if count
+                                .compare_exchange(cur_ptr, new_ptr, AcqRel, Acquire)
+                                .is_ok()
+                            {
+                                unsafe { retire(cur_ptr) };
+                                do_reclamation();
+                                break;
+                            } else {
+                                new = unsafe { Box::from_raw(new_ptr) };
                             }
-                        };
-                        sleep(Duration::from_micros(1));
-                        let value = unsafe { *cur_ptr };
-                        *new = value + 1;
-                        let new_ptr = Box::leak(new);
-                        if count
-                            .compare_exchange(cur_ptr, new_ptr, AcqRel, Acquire)
-                            .is_ok()
-                        {
-                            unsafe { retire(cur_ptr) };
-                            do_reclamation();
-                            break;
-                        } else {
-                            new = unsafe { Box::from_raw(new_ptr) };
                         }
                     }
-                }
-            });
-        }
+                    FREED.load(Relaxed)
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .min()
+            .unwrap()
     });
     let cur = count.load(Acquire);
     // exclusive access
-    assert_eq!(unsafe { *cur }, THREADS * ITER);
+    assert_eq!(unsafe { (*cur).0 }, THREADS * ITER);
+    // `do_reclamation` freed replaced values before any worker exited and flushed at thread exit.
+    assert!(freed_before_exit > 0);
     unsafe { retire(cur) };
 }
 
 #[test]
 fn stack() {
-    const THREADS: usize = 8;
-    const ITER: usize = 1024 * 16;
+    const THREADS: usize = if SHRINK { 2 } else { 8 };
+    const ITER: usize = if SHRINK { 64 } else { 1024 * 16 };
+    if SHRINK {
+        set_counts_between_flush(2);
+    }
 
     let stack = Stack::default();
     scope(|s| {
@@ -118,8 +149,11 @@ fn stack() {
 
 #[test]
 fn queue() {
-    const THREADS: usize = 8;
-    const ITER: usize = 1024 * 32;
+    const THREADS: usize = if SHRINK { 2 } else { 8 };
+    const ITER: usize = if SHRINK { 64 } else { 1024 * 32 };
+    if SHRINK {
+        set_counts_between_flush(2);
+    }
 
     let queue = Queue::default();
     scope(|s| {
@@ -137,8 +171,11 @@ fn queue() {
 
 #[test]
 fn stack_queue() {
-    const THREADS: usize = 8;
-    const ITER: usize = 1024 * 16;
+    const THREADS: usize = if SHRINK { 2 } else { 8 };
+    const ITER: usize = if SHRINK { 64 } else { 1024 * 16 };
+    if SHRINK {
+        set_counts_between_flush(2);
+    }
 
     let stack = Stack::default();
     let queue = Queue::default();
@@ -158,6 +195,32 @@ fn stack_queue() {
     assert!(stack.try_pop().is_none());
 }
 
+// Single-threaded, so that the FIFO/LIFO order can be checked and Miri explores the reclamation
+// path without interleavings.
+#[test]
+fn stack_queue_single() {
+    const ITER: usize = if SHRINK { 64 } else { 1024 * 16 };
+    if SHRINK {
+        set_counts_between_flush(2);
+    }
+
+    let stack = Stack::default();
+    let queue = Queue::default();
+    for i in 0..ITER {
+        stack.push(i);
+        stack.push(i + 1);
+        queue.push(i);
+        queue.push(i + 1);
+        assert_eq!(stack.try_pop(), Some(i + 1));
+        assert_eq!(stack.try_pop(), Some(i));
+        assert_eq!(queue.try_pop(), Some(i));
+        assert_eq!(queue.try_pop(), Some(i + 1));
+        do_reclamation();
+    }
+    assert!(stack.try_pop().is_none());
+    assert!(queue.try_pop().is_none());
+}
+
 mod stack {
     use core::mem::ManuallyDrop;
     use core::ptr;